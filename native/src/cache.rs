@@ -0,0 +1,105 @@
+use std::sync::Mutex;
+use std::time::{ Duration, Instant };
+use lazy_static::lazy_static;
+use cpal:: traits:: { DeviceTrait, HostTrait };
+
+const DEFAULT_TTL_MS: u64 = 1000;
+
+struct Snapshot {
+  taken_at: Instant,
+  names: Vec<String>,
+}
+
+struct DeviceCache {
+  enabled: bool,
+  ttl: Duration,
+  input: Option<Snapshot>,
+  output: Option<Snapshot>,
+}
+
+lazy_static! {
+  static ref DEVICE_CACHE: Mutex<DeviceCache> = Mutex::new(DeviceCache {
+    enabled: true,
+    ttl: Duration::from_millis(DEFAULT_TTL_MS),
+    input: None,
+    output: None,
+  });
+}
+
+fn enumerate(is_input: bool) -> Result<Vec<String>, String> {
+  let host = cpal::default_host();
+
+  let devices: Vec<cpal::Device> = if is_input {
+    host.input_devices().map_err(|e| e.to_string())?.collect()
+  } else {
+    host.output_devices().map_err(|e| e.to_string())?.collect()
+  };
+
+  devices.iter().map(|device| device.name().map_err(|e| e.to_string())).collect()
+}
+
+/// Names of the default host's devices for one direction, in enumeration order.
+/// Served from the last snapshot while it is younger than the TTL, unless caching is disabled.
+pub fn device_names(is_input: bool) -> Result<Vec<String>, String> {
+  {
+    let cache = DEVICE_CACHE.lock().unwrap();
+
+    if !cache.enabled {
+      drop(cache);
+
+      return enumerate(is_input);
+    }
+
+    let slot = if is_input { &cache.input } else { &cache.output };
+
+    if let Some(snapshot) = slot {
+      if snapshot.taken_at.elapsed() < cache.ttl {
+        return Ok(snapshot.names.clone());
+      }
+    }
+  }
+
+  // Enumerate without holding the lock, so a hung backend does not block every other lookup.
+  let names = enumerate(is_input)?;
+
+  let mut cache = DEVICE_CACHE.lock().unwrap();
+
+  if cache.enabled {
+    let slot = if is_input { &mut cache.input } else { &mut cache.output };
+
+    *slot = Some(Snapshot { taken_at: Instant::now(), names: names.clone() });
+  }
+
+  Ok(names)
+}
+
+/// The last snapshot for one direction regardless of its age, without enumerating.
+pub fn cached_device_names(is_input: bool) -> Option<Vec<String>> {
+  let cache = DEVICE_CACHE.lock().unwrap();
+
+  let slot = if is_input { &cache.input } else { &cache.output };
+
+  slot.as_ref().map(|snapshot| snapshot.names.clone())
+}
+
+pub fn invalidate() {
+  let mut cache = DEVICE_CACHE.lock().unwrap();
+
+  cache.input = None;
+  cache.output = None;
+}
+
+pub fn set_enabled(enabled: bool) {
+  let mut cache = DEVICE_CACHE.lock().unwrap();
+
+  cache.enabled = enabled;
+
+  if !enabled {
+    cache.input = None;
+    cache.output = None;
+  }
+}
+
+pub fn set_ttl(ttl_ms: u64) {
+  DEVICE_CACHE.lock().unwrap().ttl = Duration::from_millis(ttl_ms);
+}
@@ -1,5 +1,174 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 use neon::prelude::*;
-use cpal:: traits:: { DeviceTrait, HostTrait };
+use cpal:: traits:: { DeviceTrait, EventLoopTrait, HostTrait };
+
+use crate::cache;
+use crate::presets;
+use crate::utils::{ sample_format_from_name, sample_format_name, sample_format_range };
+
+fn index_from_number(cx: &mut FunctionContext, value: f64, what: &str) -> NeonResult<usize> {
+  // Reject negative, fractional and NaN values instead of letting `as usize` round them onto another index.
+  if value < 0.0 || value.fract() != 0.0 {
+    return cx.throw_error(format!("{} must be a non-negative integer", what));
+  }
+
+  Ok(value as usize)
+}
+
+fn device_id_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<usize> {
+  let device_id = cx.argument::<JsNumber>(i)?.value();
+
+  index_from_number(cx, device_id, "Device ID")
+}
+
+fn live_devices(is_input: bool) -> Vec<cpal::Device> {
+  let host = cpal::default_host();
+
+  if is_input {
+    host.input_devices().map(|devices| devices.collect()).unwrap_or_default()
+  } else {
+    host.output_devices().map(|devices| devices.collect()).unwrap_or_default()
+  }
+}
+
+// Device IDs are positions in the cached device lists. The live device at that position must
+// still carry the cached name, otherwise the list changed since it was cached.
+fn device_at<'a>(devices: &'a [cpal::Device], device_names: &[String], device_id: usize) -> Option<&'a cpal::Device> {
+  let device = devices.get(device_id)?;
+
+  if device.name().ok().as_ref() == device_names.get(device_id) {
+    Some(device)
+  } else {
+    None
+  }
+}
+
+fn find_device(device_id: usize, is_input: bool) -> Option<cpal::Device> {
+  let device_names = cache::device_names(is_input).ok()?;
+
+  let mut devices = live_devices(is_input);
+
+  device_at(&devices, &device_names, device_id)?;
+
+  Some(devices.swap_remove(device_id))
+}
+
+fn default_format(device: &cpal::Device, is_input: bool) -> Option<cpal::Format> {
+  if is_input {
+    device.default_input_format().ok()
+  } else {
+    device.default_output_format().ok()
+  }
+}
+
+fn format_from_object(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResult<cpal::Format> {
+  let channels = obj.get(cx, "channels")?.downcast::<JsNumber>().or_throw(cx)?.value();
+  let sample_rate = obj.get(cx, "sampleRate")?.downcast::<JsNumber>().or_throw(cx)?.value();
+  let sample_format = obj.get(cx, "sampleFormat")?.downcast::<JsString>().or_throw(cx)?.value();
+
+  let data_type = match sample_format_from_name(&sample_format) {
+    Some(data_type) => data_type,
+    None => return cx.throw_error(format!("Unknown sample format: {}", sample_format)),
+  };
+
+  Ok(cpal::Format {
+    channels: channels as u16,
+    sample_rate: cpal::SampleRate(sample_rate as u32),
+    data_type,
+  })
+}
+
+fn supported_format_from_object(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResult<cpal::SupportedFormat> {
+  let channels = obj.get(cx, "channels")?.downcast::<JsNumber>().or_throw(cx)?.value();
+  let min_sample_rate = obj.get(cx, "minSampleRate")?.downcast::<JsNumber>().or_throw(cx)?.value();
+  let max_sample_rate = obj.get(cx, "maxSampleRate")?.downcast::<JsNumber>().or_throw(cx)?.value();
+  let sample_format = obj.get(cx, "sampleFormat")?.downcast::<JsString>().or_throw(cx)?.value();
+
+  let data_type = match sample_format_from_name(&sample_format) {
+    Some(data_type) => data_type,
+    None => return cx.throw_error(format!("Unknown sample format: {}", sample_format)),
+  };
+
+  Ok(cpal::SupportedFormat {
+    channels: channels as u16,
+    min_sample_rate: cpal::SampleRate(min_sample_rate as u32),
+    max_sample_rate: cpal::SampleRate(max_sample_rate as u32),
+    data_type,
+  })
+}
+
+// `undefined` means the property is not set; any other value must have the expected type.
+fn optional_property<'a, V: Value>(cx: &mut FunctionContext<'a>, obj: Handle<'a, JsObject>, key: &str) -> NeonResult<Option<Handle<'a, V>>> {
+  let value = obj.get(cx, key)?;
+
+  if value.is_a::<JsUndefined>() {
+    return Ok(None);
+  }
+
+  match value.downcast::<V>() {
+    Ok(value) => Ok(Some(value)),
+    Err(_) => cx.throw_type_error(format!("Invalid type for {}", key)),
+  }
+}
+
+fn format_object<'a>(cx: &mut FunctionContext<'a>, format: &cpal::Format) -> Handle<'a, JsObject> {
+  let obj: Handle<JsObject> = JsObject::new(cx);
+
+  let channels = cx.number(format.channels as f64);
+  let sample_rate = cx.number(format.sample_rate.0 as f64);
+  let sample_format = cx.string(sample_format_name(format.data_type));
+
+  obj.set(cx, "channels", channels).unwrap();
+  obj.set(cx, "sampleRate", sample_rate).unwrap();
+  obj.set(cx, "sampleFormat", sample_format).unwrap();
+
+  obj
+}
+
+fn supported_formats(device: &cpal::Device, is_input: bool) -> Vec<cpal::SupportedFormat> {
+  let formats = if is_input {
+    device.supported_input_formats().map(|formats| formats.collect())
+  } else {
+    device.supported_output_formats().map(|formats| formats.collect())
+  };
+
+  formats.unwrap_or_default()
+}
+
+fn supported_config_object<'a>(cx: &mut FunctionContext<'a>, format: &cpal::SupportedFormat) -> Handle<'a, JsObject> {
+  let obj: Handle<JsObject> = JsObject::new(cx);
+
+  let channels = cx.number(format.channels as f64);
+  let min_sample_rate = cx.number(format.min_sample_rate.0 as f64);
+  let max_sample_rate = cx.number(format.max_sample_rate.0 as f64);
+  let sample_format = cx.string(sample_format_name(format.data_type));
+
+  obj.set(cx, "channels", channels).unwrap();
+  obj.set(cx, "minSampleRate", min_sample_rate).unwrap();
+  obj.set(cx, "maxSampleRate", max_sample_rate).unwrap();
+  obj.set(cx, "sampleFormat", sample_format).unwrap();
+
+  obj
+}
+
+fn channel_labels(channels: u16) -> Vec<String> {
+  let layout: &[&str] = match channels {
+    1 => &["C"],
+    2 => &["FL", "FR"],
+    4 => &["FL", "FR", "BL", "BR"],
+    6 => &["FL", "FR", "C", "LFE", "BL", "BR"],
+    8 => &["FL", "FR", "C", "LFE", "BL", "BR", "SL", "SR"],
+    _ => &[],
+  };
+
+  if layout.is_empty() {
+    (1..=channels).map(|channel| format!("ch{}", channel)).collect()
+  } else {
+    layout.iter().map(|label| label.to_string()).collect()
+  }
+}
 
 pub fn available_hosts(mut cx: FunctionContext) -> JsResult<JsArray> {
   let available_hosts = cpal::available_hosts();
@@ -21,6 +190,29 @@ pub fn available_hosts(mut cx: FunctionContext) -> JsResult<JsArray> {
   Ok(js_array)
 }
 
+pub fn default_host_id(mut cx: FunctionContext) -> JsResult<JsString> {
+  Ok(cx.string(cpal::default_host().id().name()))
+}
+
+pub fn capabilities(mut cx: FunctionContext) -> JsResult<JsObject> {
+  // Hosts compiled into this binary, whether or not they are available at runtime.
+  let host_names: Vec<&str> = cpal::ALL_HOSTS.iter().map(|host_id| host_id.name()).collect();
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  let js_array: Handle<JsArray> = cx.empty_array();
+
+  for (host_index, host_name) in host_names.iter().enumerate() {
+    let name = cx.string(host_name);
+
+    let _ = js_array.set(&mut cx, host_index as u32, name);
+  }
+
+  obj.set(&mut cx, "hosts", js_array).unwrap();
+
+  Ok(obj)
+}
+
 pub fn default_input_device(mut cx: FunctionContext) -> JsResult<JsObject> {
   match cpal::default_host().default_input_device() {
     Some(device) => {
@@ -52,17 +244,18 @@ pub fn default_output_device(mut cx: FunctionContext) -> JsResult<JsObject> {
 }
 
 pub fn input_devices(mut cx: FunctionContext) -> JsResult<JsArray> {
-  let host = cpal:: default_host();
-
-  let devices = host.input_devices().unwrap();
+  let device_names = match cache::device_names(true) {
+    Ok(device_names) => device_names,
+    Err(e) => return cx.throw_error(e),
+  };
 
   let js_array: Handle<JsArray> = cx.empty_array();
 
-  for (device_index, device) in devices.enumerate() {
+  for (device_index, device_name) in device_names.iter().enumerate() {
     let obj: Handle<JsObject> = JsObject::new(&mut cx);
 
     let index = cx.number(device_index as f64);
-    let name = cx.string(device.name().unwrap());
+    let name = cx.string(device_name);
 
     obj.set(&mut cx, "id", index).unwrap();
     obj.set(&mut cx, "name", name).unwrap();
@@ -74,17 +267,18 @@ pub fn input_devices(mut cx: FunctionContext) -> JsResult<JsArray> {
 }
 
 pub fn output_devices(mut cx: FunctionContext) -> JsResult<JsArray> {
-  let host = cpal:: default_host();
-
-  let devices = host.output_devices().unwrap();
+  let device_names = match cache::device_names(false) {
+    Ok(device_names) => device_names,
+    Err(e) => return cx.throw_error(e),
+  };
 
   let js_array: Handle<JsArray> = cx.empty_array();
 
-  for (device_index, device) in devices.enumerate() {
+  for (device_index, device_name) in device_names.iter().enumerate() {
     let obj: Handle<JsObject> = JsObject::new(&mut cx);
 
     let index = cx.number(device_index as f64);
-    let name = cx.string(device.name().unwrap());
+    let name = cx.string(device_name);
 
     obj.set(&mut cx, "id", index).unwrap();
     obj.set(&mut cx, "name", name).unwrap();
@@ -93,4 +287,752 @@ pub fn output_devices(mut cx: FunctionContext) -> JsResult<JsArray> {
   }
 
   Ok(js_array)
-}
\ No newline at end of file
+}
+
+pub fn device_system_sample_rate(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+
+  let device = match find_device(device_id, is_input) {
+    Some(device) => device,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  // Only WASAPI and CoreAudio report the device's own rate as the default format.
+  let host_name = cpal::default_host().id().name();
+
+  if host_name != "WASAPI" && host_name != "CoreAudio" {
+    return Ok(cx.null().upcast());
+  }
+
+  match default_format(&device, is_input) {
+    Some(format) => Ok(cx.number(format.sample_rate.0 as f64).upcast()),
+    None => Ok(cx.null().upcast()),
+  }
+}
+
+pub fn channel_layout(mut cx: FunctionContext) -> JsResult<JsArray> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+
+  let device = match find_device(device_id, is_input) {
+    Some(device) => device,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  // cpal does not report speaker positions, so labels follow the standard layout for the channel count.
+  let format = match default_format(&device, is_input) {
+    Some(format) => format,
+    None => return cx.throw_error("No default format for device"),
+  };
+
+  let js_array: Handle<JsArray> = cx.empty_array();
+
+  for (channel_index, label) in channel_labels(format.channels).iter().enumerate() {
+    let label = cx.string(label);
+
+    let _ = js_array.set(&mut cx, channel_index as u32, label);
+  }
+
+  Ok(js_array)
+}
+
+pub fn supported_formats_by_direction(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+
+  let device = match find_device(device_id, is_input) {
+    Some(device) => device,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  for &(key, direction_is_input) in &[("input", true), ("output", false)] {
+    let mut names: Vec<&str> = Vec::new();
+
+    for format in supported_formats(&device, direction_is_input) {
+      let name = sample_format_name(format.data_type);
+
+      if !names.contains(&name) {
+        names.push(name);
+      }
+    }
+
+    let js_array: Handle<JsArray> = cx.empty_array();
+
+    for (format_index, name) in names.iter().enumerate() {
+      let name = cx.string(name);
+
+      let _ = js_array.set(&mut cx, format_index as u32, name);
+    }
+
+    obj.set(&mut cx, key, js_array).unwrap();
+  }
+
+  Ok(obj)
+}
+
+// Position of the `occurrence`-th device called `name`, so identically named devices keep distinct IDs.
+fn nth_position(names: &[String], name: &str, occurrence: usize) -> Option<usize> {
+  names.iter()
+    .enumerate()
+    .filter(|(_, candidate)| candidate.as_str() == name)
+    .nth(occurrence)
+    .map(|(position, _)| position)
+}
+
+pub fn all_devices(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let default_host_id = cpal::default_host().id();
+
+  // The default host goes first so its devices, the only ones with usable IDs, win the dedupe.
+  let mut host_ids: Vec<cpal::HostId> = vec![default_host_id];
+  host_ids.extend(cpal::available_hosts().into_iter().filter(|host_id| *host_id != default_host_id));
+
+  let available_hosts = cpal::available_hosts();
+
+  let js_devices: Handle<JsArray> = cx.empty_array();
+  let js_errors: Handle<JsArray> = cx.empty_array();
+
+  let mut device_count: u32 = 0;
+  let mut error_count: u32 = 0;
+
+  // (name, occurrence within its host) of every device listed by an earlier host.
+  let mut seen: Vec<(String, usize)> = Vec::new();
+
+  for host_id in host_ids {
+    let host_index = available_hosts.iter().position(|available| *available == host_id);
+    let is_default_host = host_id == default_host_id;
+
+    // A host that fails to initialize or enumerate is reported in `errors` rather than failing the whole call.
+    let devices = cpal::host_from_id(host_id)
+      .map_err(|e| e.to_string())
+      .and_then(|host| host.devices().map_err(|e| e.to_string()));
+
+    let devices = match devices {
+      Ok(devices) => devices,
+      Err(e) => {
+        let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+        let host_index_value: Handle<JsValue> = match host_index {
+          Some(host_index) => cx.number(host_index as f64).upcast(),
+          None => cx.null().upcast(),
+        };
+        let error = cx.string(e);
+
+        obj.set(&mut cx, "hostId", host_index_value).unwrap();
+        obj.set(&mut cx, "error", error).unwrap();
+
+        let _ = js_errors.set(&mut cx, error_count, obj);
+
+        error_count += 1;
+
+        continue;
+      },
+    };
+
+    let (input_names, output_names) = if is_default_host {
+      (cache::device_names(true).unwrap_or_default(), cache::device_names(false).unwrap_or_default())
+    } else {
+      (Vec::new(), Vec::new())
+    };
+
+    let mut host_keys: Vec<(String, usize)> = Vec::new();
+
+    for device in devices {
+      let device_name = match device.name() {
+        Ok(device_name) => device_name,
+        Err(_) => continue,
+      };
+
+      let occurrence = host_keys.iter().filter(|(name, _)| *name == device_name).count();
+      let key = (device_name, occurrence);
+
+      // The same device exposed by several hosts is only listed once.
+      if seen.contains(&key) {
+        host_keys.push(key);
+        continue;
+      }
+
+      let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+      let host_index_value: Handle<JsValue> = match host_index {
+        Some(host_index) => cx.number(host_index as f64).upcast(),
+        None => cx.null().upcast(),
+      };
+      let name = cx.string(&key.0);
+
+      let mut direction_ids: Vec<Handle<JsValue>> = Vec::new();
+
+      for names in &[&input_names, &output_names] {
+        let id: Handle<JsValue> = match nth_position(names, &key.0, occurrence) {
+          Some(position) => cx.number(position as f64).upcast(),
+          None => cx.null().upcast(),
+        };
+
+        direction_ids.push(id);
+      }
+
+      obj.set(&mut cx, "hostId", host_index_value).unwrap();
+      obj.set(&mut cx, "name", name).unwrap();
+      obj.set(&mut cx, "inputId", direction_ids[0]).unwrap();
+      obj.set(&mut cx, "outputId", direction_ids[1]).unwrap();
+
+      let _ = js_devices.set(&mut cx, device_count, obj);
+
+      device_count += 1;
+
+      host_keys.push(key);
+    }
+
+    seen.extend(host_keys);
+  }
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  obj.set(&mut cx, "devices", js_devices).unwrap();
+  obj.set(&mut cx, "errors", js_errors).unwrap();
+
+  Ok(obj)
+}
+
+pub fn refresh_devices(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  cache::invalidate();
+
+  Ok(cx.undefined())
+}
+
+pub fn set_device_cache_enabled(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let enabled = cx.argument::<JsBoolean>(0)?.value();
+
+  cache::set_enabled(enabled);
+
+  Ok(cx.undefined())
+}
+
+pub fn set_device_cache_ttl(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let ttl_ms = cx.argument::<JsNumber>(0)?.value();
+
+  if ttl_ms.is_nan() || ttl_ms < 0.0 {
+    return cx.throw_error("TTL must be a non-negative number of milliseconds");
+  }
+
+  cache::set_ttl(ttl_ms as u64);
+
+  Ok(cx.undefined())
+}
+
+pub fn resolve_device(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+
+  let device_name = cache::cached_device_names(is_input)
+    .and_then(|device_names| device_names.into_iter().nth(device_id));
+
+  match device_name {
+    Some(device_name) => {
+      let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+      let index = cx.number(device_id as f64);
+      let name = cx.string(device_name);
+
+      obj.set(&mut cx, "id", index).unwrap();
+      obj.set(&mut cx, "name", name).unwrap();
+
+      Ok(obj.upcast())
+    },
+    None => Ok(cx.null().upcast()),
+  }
+}
+
+pub fn usable_devices(mut cx: FunctionContext) -> JsResult<JsArray> {
+  let is_input = cx.argument::<JsBoolean>(0)?.value();
+
+  let device_names = match cache::device_names(is_input) {
+    Ok(device_names) => device_names,
+    Err(e) => return cx.throw_error(e),
+  };
+
+  let devices = live_devices(is_input);
+
+  let js_array: Handle<JsArray> = cx.empty_array();
+
+  let mut usable_count: u32 = 0;
+
+  for (device_index, device_name) in device_names.iter().enumerate() {
+    // A device that cannot report a default format for the direction cannot be opened either.
+    let is_usable = device_at(&devices, &device_names, device_index)
+      .is_some_and(|device| default_format(device, is_input).is_some());
+
+    if !is_usable {
+      continue;
+    }
+
+    let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+    let index = cx.number(device_index as f64);
+    let name = cx.string(device_name);
+
+    obj.set(&mut cx, "id", index).unwrap();
+    obj.set(&mut cx, "name", name).unwrap();
+
+    let _ = js_array.set(&mut cx, usable_count, obj);
+
+    usable_count += 1;
+  }
+
+  Ok(js_array)
+}
+
+pub fn native_config(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+
+  let device = match find_device(device_id, is_input) {
+    Some(device) => device,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  let format = match default_format(&device, is_input) {
+    Some(format) => format,
+    None => return cx.throw_error("No default format for device"),
+  };
+
+  Ok(format_object(&mut cx, &format))
+}
+
+pub fn supported_configs_for_devices(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let device_ids = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+  let with_max_sample_rate = match cx.argument_opt(2) {
+    Some(arg) if !arg.is_a::<JsUndefined>() => arg.downcast::<JsBoolean>().or_throw(&mut cx)?.value(),
+    _ => false,
+  };
+
+  let device_names = match cache::device_names(is_input) {
+    Ok(device_names) => device_names,
+    Err(e) => return cx.throw_error(e),
+  };
+
+  // Enumerate once and match every requested ID against the same device list.
+  let devices = live_devices(is_input);
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  for device_id in device_ids {
+    let device_id = device_id.downcast::<JsNumber>().or_throw(&mut cx)?.value();
+    let device_id = index_from_number(&mut cx, device_id, "Device ID")?;
+
+    let device = device_at(&devices, &device_names, device_id);
+
+    let key = device_id.to_string();
+
+    match device {
+      Some(device) => {
+        let js_array: Handle<JsArray> = cx.empty_array();
+
+        for (config_index, format) in supported_formats(device, is_input).iter().enumerate() {
+          let config = supported_config_object(&mut cx, format);
+
+          // The concrete config cpal expects to build a stream with, alongside the range.
+          if with_max_sample_rate {
+            let sample_rate = cx.number(format.clone().with_max_sample_rate().sample_rate.0 as f64);
+
+            config.set(&mut cx, "sampleRate", sample_rate).unwrap();
+          }
+
+          let _ = js_array.set(&mut cx, config_index as u32, config);
+        }
+
+        obj.set(&mut cx, key.as_str(), js_array).unwrap();
+      },
+      None => {
+        let null = cx.null();
+
+        obj.set(&mut cx, key.as_str(), null).unwrap();
+      },
+    }
+  }
+
+  Ok(obj)
+}
+
+pub fn probe_stream(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+  let config = cx.argument::<JsObject>(2)?;
+
+  let format = format_from_object(&mut cx, config)?;
+
+  let device = match find_device(device_id, is_input) {
+    Some(device) => device,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  let event_loop = cpal::default_host().event_loop();
+
+  let stream_id = if is_input {
+    event_loop.build_input_stream(&device, &format)
+  } else {
+    event_loop.build_output_stream(&device, &format)
+  };
+
+  // The stream is never played and is destroyed as soon as it is built.
+  let result = stream_id.map(|stream_id| event_loop.destroy_stream(stream_id));
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  let ok = cx.boolean(result.is_ok());
+  let requested = format_object(&mut cx, &format);
+  let error: Handle<JsValue> = match result {
+    Ok(()) => cx.null().upcast(),
+    Err(e) => cx.string(e.to_string()).upcast(),
+  };
+
+  obj.set(&mut cx, "ok", ok).unwrap();
+  obj.set(&mut cx, "requested", requested).unwrap();
+  obj.set(&mut cx, "error", error).unwrap();
+
+  Ok(obj)
+}
+
+pub fn device_capabilities(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+
+  let (input_names, output_names) = match (cache::device_names(true), cache::device_names(false)) {
+    (Ok(input_names), Ok(output_names)) => (input_names, output_names),
+    (Err(e), _) | (_, Err(e)) => return cx.throw_error(e),
+  };
+
+  let own_names = if is_input { &input_names } else { &output_names };
+
+  let device_name = match own_names.get(device_id) {
+    Some(device_name) => device_name,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  // A device's directions are the cached device lists it appears in, matched by name and by
+  // occurrence so identically named devices are told apart.
+  let occurrence = own_names[..device_id].iter().filter(|name| *name == device_name).count();
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  let input = cx.boolean(nth_position(&input_names, device_name, occurrence).is_some());
+  let output = cx.boolean(nth_position(&output_names, device_name, occurrence).is_some());
+
+  obj.set(&mut cx, "input", input).unwrap();
+  obj.set(&mut cx, "output", output).unwrap();
+
+  Ok(obj)
+}
+
+pub fn supported_formats_for(mut cx: FunctionContext) -> JsResult<JsArray> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+  let channels = cx.argument::<JsNumber>(2)?.value() as u16;
+  let sample_rate = cx.argument::<JsNumber>(3)?.value() as u32;
+
+  let device = match find_device(device_id, is_input) {
+    Some(device) => device,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  let mut names: Vec<&str> = Vec::new();
+
+  for format in supported_formats(&device, is_input) {
+    let matches = format.channels == channels
+      && format.min_sample_rate.0 <= sample_rate
+      && sample_rate <= format.max_sample_rate.0;
+
+    let name = sample_format_name(format.data_type);
+
+    if matches && !names.contains(&name) {
+      names.push(name);
+    }
+  }
+
+  let js_array: Handle<JsArray> = cx.empty_array();
+
+  for (format_index, name) in names.iter().enumerate() {
+    let name = cx.string(name);
+
+    let _ = js_array.set(&mut cx, format_index as u32, name);
+  }
+
+  Ok(js_array)
+}
+
+pub fn compare_configs(mut cx: FunctionContext) -> JsResult<JsNumber> {
+  let a = cx.argument::<JsObject>(0)?;
+  let b = cx.argument::<JsObject>(1)?;
+
+  let a = supported_format_from_object(&mut cx, a)?;
+  let b = supported_format_from_object(&mut cx, b)?;
+
+  // Positive when `a` is the better default according to cpal.
+  let ordering = match a.cmp_default_heuristics(&b) {
+    std::cmp::Ordering::Less => -1,
+    std::cmp::Ordering::Equal => 0,
+    std::cmp::Ordering::Greater => 1,
+  };
+
+  Ok(cx.number(ordering as f64))
+}
+
+pub fn pick_default_from_supported(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let configs = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+
+  let mut best: Option<(Handle<JsObject>, cpal::SupportedFormat)> = None;
+
+  for config in configs {
+    let config = config.downcast::<JsObject>().or_throw(&mut cx)?;
+    let format = supported_format_from_object(&mut cx, config)?;
+
+    // Ties go to the later config, as cpal takes the last of its stably sorted formats.
+    let is_better = match &best {
+      Some((_, best_format)) => format.cmp_default_heuristics(best_format) != std::cmp::Ordering::Less,
+      None => true,
+    };
+
+    if is_better {
+      best = Some((config, format));
+    }
+  }
+
+  match best {
+    Some((config, _)) => Ok(config.upcast()),
+    None => Ok(cx.null().upcast()),
+  }
+}
+
+pub fn devices_with_timeout(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let host_index = cx.argument::<JsNumber>(0)?.value();
+  let host_index = index_from_number(&mut cx, host_index, "Host ID")?;
+  let timeout_ms = cx.argument::<JsNumber>(1)?.value();
+
+  if timeout_ms.is_nan() || timeout_ms < 0.0 {
+    return cx.throw_error("Timeout must be a non-negative number of milliseconds");
+  }
+
+  let host_id = match cpal::available_hosts().get(host_index) {
+    Some(host_id) => *host_id,
+    None => return cx.throw_error("Host not found"),
+  };
+
+  let (sender, receiver) = mpsc::channel();
+
+  // If enumeration hangs the thread is left behind; it only ever sends into a dropped receiver.
+  thread::spawn(move || {
+    let names = |devices: Vec<cpal::Device>| -> Result<Vec<String>, String> {
+      devices.iter().map(|device| device.name().map_err(|e| e.to_string())).collect()
+    };
+
+    let device_names = cpal::host_from_id(host_id)
+      .map_err(|e| e.to_string())
+      .and_then(|host| {
+        let input_devices = host.input_devices().map_err(|e| e.to_string())?.collect();
+        let output_devices = host.output_devices().map_err(|e| e.to_string())?.collect();
+
+        Ok((names(input_devices)?, names(output_devices)?))
+      });
+
+    let _ = sender.send(device_names);
+  });
+
+  let (input_names, output_names) = match receiver.recv_timeout(Duration::from_millis(timeout_ms as u64)) {
+    Ok(Ok(device_names)) => device_names,
+    Ok(Err(e)) => return cx.throw_error(e),
+    Err(_) => {
+      let error = JsError::error(&mut cx, "Device enumeration timed out")?;
+      let code = cx.string("ETIMEDOUT");
+
+      error.set(&mut cx, "code", code)?;
+
+      return cx.throw(error);
+    },
+  };
+
+  // IDs are positions in each direction's list for this host, so they match the IDs the other
+  // functions accept only when `hostId` is the default host.
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  for &(key, device_names) in &[("input", &input_names), ("output", &output_names)] {
+    let js_array: Handle<JsArray> = cx.empty_array();
+
+    for (device_index, device_name) in device_names.iter().enumerate() {
+      let device = JsObject::new(&mut cx);
+
+      let index = cx.number(device_index as f64);
+      let name = cx.string(device_name);
+
+      device.set(&mut cx, "id", index).unwrap();
+      device.set(&mut cx, "name", name).unwrap();
+
+      let _ = js_array.set(&mut cx, device_index as u32, device);
+    }
+
+    obj.set(&mut cx, key, js_array).unwrap();
+  }
+
+  Ok(obj)
+}
+
+fn preset_key(device_id: usize, is_input: bool) -> Option<presets::PresetKey> {
+  find_device(device_id, is_input)?;
+
+  let device_names = cache::device_names(is_input).ok()?;
+  let device_name = device_names.get(device_id)?;
+
+  let occurrence = device_names[..device_id].iter().filter(|name| *name == device_name).count();
+
+  Some((is_input, device_name.clone(), occurrence))
+}
+
+pub fn save_device_preset(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+  let config = cx.argument::<JsObject>(2)?;
+
+  let format = format_from_object(&mut cx, config)?;
+
+  let key = match preset_key(device_id, is_input) {
+    Some(key) => key,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  presets::save(key, format);
+
+  Ok(cx.undefined())
+}
+
+pub fn device_preset(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+
+  let key = match preset_key(device_id, is_input) {
+    Some(key) => key,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  match presets::get(&key) {
+    Some(format) => Ok(format_object(&mut cx, &format).upcast()),
+    None => Ok(cx.null().upcast()),
+  }
+}
+
+pub fn would_resample(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let device_id = device_id_argument(&mut cx, 0)?;
+  let is_input = cx.argument::<JsBoolean>(1)?.value();
+  let sample_rate = cx.argument::<JsNumber>(2)?.value() as u32;
+
+  let device = match find_device(device_id, is_input) {
+    Some(device) => device,
+    None => return cx.throw_error("Device not found"),
+  };
+
+  let format = match default_format(&device, is_input) {
+    Some(format) => format,
+    None => return cx.throw_error("No default format for device"),
+  };
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  let resample = cx.boolean(format.sample_rate.0 != sample_rate);
+  let native_sample_rate = cx.number(format.sample_rate.0 as f64);
+
+  obj.set(&mut cx, "resample", resample).unwrap();
+  obj.set(&mut cx, "nativeSampleRate", native_sample_rate).unwrap();
+
+  Ok(obj)
+}
+
+pub fn format_info(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let sample_format = cx.argument::<JsString>(0)?.value();
+
+  let sample_format = match sample_format_from_name(&sample_format) {
+    Some(sample_format) => sample_format,
+    None => return cx.throw_error(format!("Unknown sample format: {}", sample_format)),
+  };
+
+  let (min, max) = sample_format_range(sample_format);
+
+  let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+  let bytes = cx.number(sample_format.sample_size() as f64);
+  let signed = cx.boolean(sample_format != cpal::SampleFormat::U16);
+  let float = cx.boolean(sample_format == cpal::SampleFormat::F32);
+  let min = cx.number(min);
+  let max = cx.number(max);
+
+  obj.set(&mut cx, "bytes", bytes).unwrap();
+  obj.set(&mut cx, "signed", signed).unwrap();
+  obj.set(&mut cx, "float", float).unwrap();
+  obj.set(&mut cx, "min", min).unwrap();
+  obj.set(&mut cx, "max", max).unwrap();
+
+  Ok(obj)
+}
+
+pub fn filtered_devices(mut cx: FunctionContext) -> JsResult<JsArray> {
+  let criteria = cx.argument::<JsObject>(0)?;
+
+  let min_channels = optional_property::<JsNumber>(&mut cx, criteria, "minChannels")?.map(|n| n.value() as u16);
+  let min_sample_rate = optional_property::<JsNumber>(&mut cx, criteria, "minSampleRate")?.map(|n| n.value() as u32);
+  let format = optional_property::<JsString>(&mut cx, criteria, "format")?.map(|s| s.value());
+  let direction = optional_property::<JsString>(&mut cx, criteria, "direction")?.map(|s| s.value());
+
+  let is_input = match direction.as_deref() {
+    Some("input") => true,
+    Some("output") | None => false,
+    Some(direction) => return cx.throw_error(format!("Unknown direction: {}", direction)),
+  };
+
+  let data_type = match format {
+    Some(format) => match sample_format_from_name(&format) {
+      Some(data_type) => Some(data_type),
+      None => return cx.throw_error(format!("Unknown sample format: {}", format)),
+    },
+    None => None,
+  };
+
+  let device_names = match cache::device_names(is_input) {
+    Ok(device_names) => device_names,
+    Err(e) => return cx.throw_error(e),
+  };
+
+  let devices = live_devices(is_input);
+
+  let js_array: Handle<JsArray> = cx.empty_array();
+
+  let mut matching_count: u32 = 0;
+
+  for (device_index, device_name) in device_names.iter().enumerate() {
+    // A device matches when a single supported config satisfies every criterion.
+    let is_match = device_at(&devices, &device_names, device_index).is_some_and(|device| {
+      supported_formats(device, is_input).iter().any(|supported| {
+        min_channels.is_none_or(|channels| supported.channels >= channels)
+          && min_sample_rate.is_none_or(|sample_rate| supported.max_sample_rate.0 >= sample_rate)
+          && data_type.is_none_or(|data_type| supported.data_type == data_type)
+      })
+    });
+
+    if !is_match {
+      continue;
+    }
+
+    let obj: Handle<JsObject> = JsObject::new(&mut cx);
+
+    let index = cx.number(device_index as f64);
+    let name = cx.string(device_name);
+
+    obj.set(&mut cx, "id", index).unwrap();
+    obj.set(&mut cx, "name", name).unwrap();
+
+    let _ = js_array.set(&mut cx, matching_count, obj);
+
+    matching_count += 1;
+  }
+
+  Ok(js_array)
+}
@@ -1,6 +1,9 @@
 use neon::prelude::*;
 
+mod cache;
 mod funcs;
+mod presets;
+mod utils;
 
 register_module!(mut cx, {
   cx.export_function("findAvailableHosts", funcs::available_hosts).unwrap();
@@ -8,5 +11,30 @@ register_module!(mut cx, {
   cx.export_function("findDefaultOutputDevice", funcs::default_output_device).unwrap();
   cx.export_function("findInputDevices", funcs::input_devices).unwrap();
   cx.export_function("findOutputDevices", funcs::output_devices).unwrap();
+  cx.export_function("getDeviceSystemSampleRate", funcs::device_system_sample_rate).unwrap();
+  cx.export_function("getChannelLayout", funcs::channel_layout).unwrap();
+  cx.export_function("getSupportedFormats", funcs::supported_formats_by_direction).unwrap();
+  cx.export_function("getAllDevices", funcs::all_devices).unwrap();
+  cx.export_function("refreshDevices", funcs::refresh_devices).unwrap();
+  cx.export_function("setDeviceCacheTtl", funcs::set_device_cache_ttl).unwrap();
+  cx.export_function("resolveDevice", funcs::resolve_device).unwrap();
+  cx.export_function("getUsableDevices", funcs::usable_devices).unwrap();
+  cx.export_function("setDeviceCacheEnabled", funcs::set_device_cache_enabled).unwrap();
+  cx.export_function("getNativeConfig", funcs::native_config).unwrap();
+  cx.export_function("getSupportedConfigsForDevices", funcs::supported_configs_for_devices).unwrap();
+  cx.export_function("probeStream", funcs::probe_stream).unwrap();
+  cx.export_function("getDefaultHostId", funcs::default_host_id).unwrap();
+  cx.export_function("getDeviceCapabilities", funcs::device_capabilities).unwrap();
+  cx.export_function("getSupportedFormatsFor", funcs::supported_formats_for).unwrap();
+  cx.export_function("compareConfigs", funcs::compare_configs).unwrap();
+  cx.export_function("pickDefaultFromSupported", funcs::pick_default_from_supported).unwrap();
+  cx.export_function("getDevicesWithTimeout", funcs::devices_with_timeout).unwrap();
+  cx.export_function("saveDevicePreset", funcs::save_device_preset).unwrap();
+  cx.export_function("getDevicePreset", funcs::device_preset).unwrap();
+  cx.export_function("wouldResample", funcs::would_resample).unwrap();
+  cx.export_function("getFormatInfo", funcs::format_info).unwrap();
+  cx.export_function("findDevices", funcs::filtered_devices).unwrap();
+  cx.export_function("getCapabilities", funcs::capabilities).unwrap();
+  cx.export_function("clearDeviceCache", funcs::refresh_devices).unwrap();
   Ok(())
 });
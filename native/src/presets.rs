@@ -0,0 +1,19 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+
+// Direction, device name and the device's occurrence among identically named devices.
+pub type PresetKey = (bool, String, usize);
+
+lazy_static! {
+  // Not keyed by position, so a preset survives devices being added or removed before it.
+  static ref PRESETS: Mutex<HashMap<PresetKey, cpal::Format>> = Mutex::new(HashMap::new());
+}
+
+pub fn save(key: PresetKey, format: cpal::Format) {
+  PRESETS.lock().unwrap().insert(key, format);
+}
+
+pub fn get(key: &PresetKey) -> Option<cpal::Format> {
+  PRESETS.lock().unwrap().get(key).cloned()
+}
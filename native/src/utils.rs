@@ -0,0 +1,25 @@
+pub fn sample_format_name(sample_format: cpal::SampleFormat) -> &'static str {
+  match sample_format {
+    cpal::SampleFormat::I16 => "i16",
+    cpal::SampleFormat::U16 => "u16",
+    cpal::SampleFormat::F32 => "f32",
+  }
+}
+
+pub fn sample_format_from_name(name: &str) -> Option<cpal::SampleFormat> {
+  match name {
+    "i16" => Some(cpal::SampleFormat::I16),
+    "u16" => Some(cpal::SampleFormat::U16),
+    "f32" => Some(cpal::SampleFormat::F32),
+    _ => None,
+  }
+}
+
+/// Smallest and largest sample value of a format.
+pub fn sample_format_range(sample_format: cpal::SampleFormat) -> (f64, f64) {
+  match sample_format {
+    cpal::SampleFormat::I16 => (i16::MIN as f64, i16::MAX as f64),
+    cpal::SampleFormat::U16 => (u16::MIN as f64, u16::MAX as f64),
+    cpal::SampleFormat::F32 => (-1.0, 1.0),
+  }
+}
@@ -16,6 +16,89 @@ try {
 	const outputDevices = cpal.findOutputDevices();
 	console.log('outputDevices', outputDevices);
 
+	cpal.setDeviceCacheTtl(0);
+	cpal.refreshDevices();
+
+	const refreshedOutputDevices = cpal.findOutputDevices();
+	console.log('refreshedOutputDevices', refreshedOutputDevices);
+
+	cpal.setDeviceCacheEnabled(false);
+
+	const uncachedInputDevices = cpal.findInputDevices();
+	console.log('uncachedInputDevices', uncachedInputDevices);
+
+	cpal.setDeviceCacheEnabled(true);
+	cpal.clearDeviceCache();
+	cpal.setDeviceCacheTtl(1000);
+
+	const deviceSystemSampleRate = cpal.getDeviceSystemSampleRate(0, false);
+	console.log('deviceSystemSampleRate', deviceSystemSampleRate);
+
+	const channelLayout = cpal.getChannelLayout(0, false);
+	console.log('channelLayout', channelLayout);
+
+	const supportedFormats = cpal.getSupportedFormats(0, false);
+	console.log('supportedFormats', supportedFormats);
+
+	const allDevices = cpal.getAllDevices();
+	console.log('allDevices', allDevices);
+
+	const resolvedDevice = cpal.resolveDevice(0, false);
+	console.log('resolvedDevice', resolvedDevice);
+
+	const usableDevices = cpal.getUsableDevices(false);
+	console.log('usableDevices', usableDevices);
+
+	const nativeConfig = cpal.getNativeConfig(0, false);
+	console.log('nativeConfig', nativeConfig);
+
+	const supportedConfigsForDevices = cpal.getSupportedConfigsForDevices([0], false);
+	console.log('supportedConfigsForDevices', supportedConfigsForDevices);
+
+	const concreteConfigsForDevices = cpal.getSupportedConfigsForDevices([0], false, true);
+	console.log('concreteConfigsForDevices', concreteConfigsForDevices);
+
+	const probedStream = cpal.probeStream(0, false, cpal.getNativeConfig(0, false));
+	console.log('probedStream', probedStream);
+
+	const defaultHostId = cpal.getDefaultHostId();
+	console.log('defaultHostId', defaultHostId);
+
+	const deviceCapabilities = cpal.getDeviceCapabilities(0, false);
+	console.log('deviceCapabilities', deviceCapabilities);
+
+	const supportedFormatsFor = cpal.getSupportedFormatsFor(0, false, 2, 48000);
+	console.log('supportedFormatsFor', supportedFormatsFor);
+
+	const defaultFromSupported = cpal.pickDefaultFromSupported(cpal.getSupportedConfigsForDevices([0], false)[0] || []);
+	console.log('defaultFromSupported', defaultFromSupported);
+
+	const configComparison = cpal.compareConfigs(
+		{ channels: 2, minSampleRate: 44100, maxSampleRate: 48000, sampleFormat: 'f32' },
+		{ channels: 2, minSampleRate: 44100, maxSampleRate: 48000, sampleFormat: 'i16' }
+	);
+	console.log('configComparison', configComparison);
+
+	const devicesWithTimeout = cpal.getDevicesWithTimeout(0, 5000);
+	console.log('devicesWithTimeout', devicesWithTimeout);
+
+	cpal.saveDevicePreset(0, false, cpal.getNativeConfig(0, false));
+
+	const devicePreset = cpal.getDevicePreset(0, false);
+	console.log('devicePreset', devicePreset);
+
+	const resampleCheck = cpal.wouldResample(0, false, 44100);
+	console.log('resampleCheck', resampleCheck);
+
+	const formatInfo = cpal.getFormatInfo('i16');
+	console.log('formatInfo', formatInfo);
+
+	const filteredDevices = cpal.findDevices({ minChannels: 2, direction: 'output' });
+	console.log('filteredDevices', filteredDevices);
+
+	const capabilities = cpal.getCapabilities();
+	console.log('capabilities', capabilities);
+
 	process.exit(0);
 } catch (e) {
 	console.error(e);